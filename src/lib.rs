@@ -1,14 +1,16 @@
 #[cfg(unix)]
 mod unix;
 #[cfg(unix)]
-pub use unix::read_key;
+pub use unix::{read_key, read_paste, term_width};
 
 #[cfg(windows)]
 mod windows;
 #[cfg(windows)]
-pub use windows::read_key;
+pub use windows::{read_key, read_paste, term_width};
 
 use std::io::{self, Write};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
 const CLEAR: &str = "\x1b[2K";
 
@@ -25,6 +27,310 @@ pub enum Key {
   CtrlBackspace,
   CtrlArrowRight,
   CtrlArrowLeft,
+  CtrlA,
+  CtrlE,
+  CtrlF,
+  CtrlB,
+  CtrlK,
+  CtrlU,
+  CtrlY,
+  MetaY,
+  Tab,
+  CtrlR,
+  CtrlG,
+  PasteStart,
+  Escape,
+}
+
+/// A single editing or navigation command, resolved from a `Key` by the active [`Keymap`].
+/// [`readch`] applies whichever variant comes back without needing to know which profile
+/// produced it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+  InsertChar(char),
+  DeleteCharBackward,
+  DeleteCharForward,
+  MoveBackwardChar,
+  MoveForwardChar,
+  MoveBackwardWord,
+  MoveForwardWord,
+  KillWordBackward,
+  KillLine,
+  KillLineBackward,
+  Yank,
+  YankPop,
+  BeginningOfLine,
+  EndOfLine,
+  Complete,
+  Paste,
+  EnterNormalMode,
+  EnterInsertMode,
+  EnterInsertModeAfter,
+  Noop,
+}
+
+/// Whether the [`Keymap::Vi`] profile is currently accepting typed characters as text
+/// (insert mode) or as normal-mode commands. Unused by [`Keymap::Emacs`], which has no modes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ViState {
+  insert: bool,
+}
+
+impl ViState {
+  /// Vi starts in insert mode, the same as most shells' line editors.
+  pub fn new() -> Self {
+    Self { insert: true }
+  }
+}
+
+impl Default for ViState {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// A key-binding profile that resolves a `Key` to the [`Action`] it should perform.
+/// `readln_with` and `pushln_with` take one of these to select how keys are interpreted
+/// (`readln` and `pushln` always use `Keymap::Emacs`); `readch` resolves every key it reads
+/// through whichever profile was passed in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Keymap {
+  /// Ctrl-A/Ctrl-E for line start/end, Ctrl-F/Ctrl-B for char movement, Ctrl-W (via
+  /// `Key::CtrlBackspace`) for word kill. No modes: every key always inserts or edits directly.
+  Emacs,
+  /// Modal, like Vi: starts in insert mode; `Escape` enters normal mode, where `h`/`l` move,
+  /// `w`/`b` jump words, `x` deletes the character under the cursor, `i`/`a` re-enter insert
+  /// mode (before/after the cursor), and `0`/`$` jump to the start/end of the line.
+  Vi,
+}
+
+impl Keymap {
+  fn resolve(self, key: Key, vi: &ViState) -> Action {
+    if let Some(action) = Self::resolve_common(key) {
+      return action;
+    }
+
+    match self {
+      Keymap::Emacs => Self::resolve_emacs(key),
+      Keymap::Vi if vi.insert => Self::resolve_vi_insert(key),
+      Keymap::Vi => Self::resolve_vi_normal(key),
+    }
+  }
+
+  /// Bindings shared by every profile: kill-ring, completion, paste, deletion and movement keys
+  /// that aren't part of what makes a keymap "Emacs" or "Vi".
+  fn resolve_common(key: Key) -> Option<Action> {
+    Some(match key {
+      Key::Backspace => Action::DeleteCharBackward,
+      Key::ArrowLeft => Action::MoveBackwardChar,
+      Key::ArrowRight => Action::MoveForwardChar,
+      Key::CtrlBackspace => Action::KillWordBackward,
+      Key::CtrlArrowLeft => Action::MoveBackwardWord,
+      Key::CtrlArrowRight => Action::MoveForwardWord,
+      Key::CtrlK => Action::KillLine,
+      Key::CtrlU => Action::KillLineBackward,
+      Key::CtrlY => Action::Yank,
+      Key::MetaY => Action::YankPop,
+      Key::Tab => Action::Complete,
+      Key::PasteStart => Action::Paste,
+      _ => return None,
+    })
+  }
+
+  fn resolve_emacs(key: Key) -> Action {
+    match key {
+      Key::Char(ch) => Action::InsertChar(ch),
+      Key::CtrlA => Action::BeginningOfLine,
+      Key::CtrlE => Action::EndOfLine,
+      Key::CtrlF => Action::MoveForwardChar,
+      Key::CtrlB => Action::MoveBackwardChar,
+      _ => Action::Noop,
+    }
+  }
+
+  fn resolve_vi_insert(key: Key) -> Action {
+    match key {
+      Key::Char(ch) => Action::InsertChar(ch),
+      Key::Escape => Action::EnterNormalMode,
+      _ => Action::Noop,
+    }
+  }
+
+  fn resolve_vi_normal(key: Key) -> Action {
+    match key {
+      Key::Char('h') => Action::MoveBackwardChar,
+      Key::Char('l') => Action::MoveForwardChar,
+      Key::Char('w') => Action::MoveForwardWord,
+      Key::Char('b') => Action::MoveBackwardWord,
+      Key::Char('x') => Action::DeleteCharForward,
+      Key::Char('i') => Action::EnterInsertMode,
+      Key::Char('a') => Action::EnterInsertModeAfter,
+      Key::Char('0') => Action::BeginningOfLine,
+      Key::Char('$') => Action::EndOfLine,
+      _ => Action::Noop,
+    }
+  }
+}
+
+const PASTE_ENABLE: &str = "\x1b[?2004h";
+const PASTE_DISABLE: &str = "\x1b[?2004l";
+
+/// Enables bracketed paste on construction and disables it again on drop, so it's restored
+/// even if the editing loop returns early through `?`.
+struct BracketedPasteGuard;
+
+impl BracketedPasteGuard {
+  fn enable() -> io::Result<Self> {
+    print!("{}", PASTE_ENABLE);
+    io::stdout().flush()?;
+    Ok(Self)
+  }
+}
+
+impl Drop for BracketedPasteGuard {
+  fn drop(&mut self) {
+    print!("{}", PASTE_DISABLE);
+    let _ = io::stdout().flush();
+  }
+}
+
+/// Provides completion candidates for the word under the cursor, modeled on rustyline's
+/// `Completer` trait.
+pub trait Completer {
+  /// Returns the byte offset in `line` the completion should be spliced in at, along with the
+  /// list of candidate replacements for the word starting there.
+  fn complete(&self, line: &str, pos: usize) -> (usize, Vec<String>);
+}
+
+/// State carried across [`readch`] calls so a second consecutive `Tab` (with no other edit in
+/// between) shows the full candidate list instead of just narrowing to the common prefix.
+#[derive(Debug, Default)]
+pub struct CompletionState {
+  pending: bool,
+}
+
+impl CompletionState {
+  pub fn new() -> Self {
+    Self::default()
+  }
+}
+
+/// Normalizes `\r\n` and bare `\r` line endings to `\n` in a pasted payload, the same way
+/// embedded `\n` is already treated as a literal inserted newline rather than an `Enter` that
+/// submits the line: an un-normalized `\r` would instead make the terminal jump to column 0
+/// mid-redraw, desyncing it from [`visual_rows`]/[`cursor_row_col`], which only account for
+/// `\n`. Shared by both platforms' `read_paste`.
+pub(crate) fn normalize_paste_newlines(bytes: &[u8]) -> Vec<u8> {
+  let mut out = Vec::with_capacity(bytes.len());
+  let mut iter = bytes.iter().copied().peekable();
+  while let Some(b) = iter.next() {
+    if b == b'\r' {
+      if iter.peek() == Some(&b'\n') {
+        iter.next();
+      }
+      out.push(b'\n');
+    } else {
+      out.push(b);
+    }
+  }
+  out
+}
+
+/// Longest prefix shared by every candidate, compared by `char` so multi-byte UTF-8 sequences
+/// are never split.
+fn longest_common_prefix(candidates: &[String]) -> String {
+  let mut prefix: Vec<char> = candidates[0].chars().collect();
+  for candidate in &candidates[1..] {
+    let shared = prefix.iter().zip(candidate.chars()).take_while(|(a, b)| **a == *b).count();
+    prefix.truncate(shared);
+  }
+  prefix.into_iter().collect()
+}
+
+/// Direction a span of text was removed from the buffer in, used to decide whether two
+/// consecutive kills should merge into a single ring entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KillDir {
+  Forward,
+  Backward,
+}
+
+const KILL_RING_CAPACITY: usize = 16;
+
+/// A bounded ring buffer of killed text, mirroring the kill ring of classic `readline`.
+///
+/// Consecutive kills made in the same direction (e.g. repeated `Ctrl-K`) are merged into a
+/// single ring entry instead of each push becoming its own entry. [`readch`] uses this to
+/// implement kill (Ctrl-K/Ctrl-U/Ctrl-Backspace), yank (Ctrl-Y), and yank-pop (Meta-Y).
+#[derive(Debug, Default)]
+pub struct KillRing {
+  ring: Vec<String>,
+  yank_idx: usize,
+  last_kill: Option<KillDir>,
+  last_yank_len: Option<usize>,
+}
+
+impl KillRing {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Records a killed span, merging it into the top entry if the previous action was a kill
+  /// in the same direction.
+  fn push(&mut self, text: &str, dir: KillDir) {
+    self.last_yank_len = None;
+    if text.is_empty() {
+      return;
+    }
+
+    match (self.last_kill, self.ring.last_mut()) {
+      (Some(prev_dir), Some(top)) if prev_dir == dir => match dir {
+        KillDir::Forward => top.push_str(text),
+        KillDir::Backward => top.insert_str(0, text),
+      },
+      _ => {
+        self.ring.push(text.to_owned());
+        if self.ring.len() > KILL_RING_CAPACITY {
+          self.ring.remove(0);
+        }
+      }
+    }
+
+    self.yank_idx = self.ring.len() - 1;
+    self.last_kill = Some(dir);
+  }
+
+  /// Inserts the most recent kill at `pos`, remembering its length so a following yank-pop
+  /// can replace it.
+  fn yank(&mut self, buf: &mut String, pos: &mut usize) {
+    self.last_kill = None;
+    let Some(text) = self.ring.last() else {
+      return;
+    };
+
+    self.yank_idx = self.ring.len() - 1;
+    buf.insert_str(*pos, text);
+    *pos += text.len();
+    self.last_yank_len = Some(text.len());
+  }
+
+  /// Replaces the text inserted by the immediately preceding yank with the next-older ring
+  /// entry. Does nothing if the previous action was not a yank.
+  fn yank_pop(&mut self, buf: &mut String, pos: &mut usize) {
+    let Some(yanked_len) = self.last_yank_len else {
+      return;
+    };
+    if self.ring.is_empty() {
+      return;
+    }
+
+    self.yank_idx = (self.yank_idx + self.ring.len() - 1) % self.ring.len();
+    let start = *pos - yanked_len;
+    let text = self.ring[self.yank_idx].clone();
+    buf.replace_range(start..*pos, &text);
+    *pos = start + text.len();
+    self.last_yank_len = Some(text.len());
+  }
 }
 
 /// Reads user input in a loop with a customizable prompt and a command history.
@@ -74,21 +380,42 @@ pub enum Key {
 /// - `Key::Enter`: Accepts the current input and ends the input loop.
 /// - `Key::ArrowUp`: Navigates to the previous input in the history.
 /// - `Key::ArrowDown`: Navigates to the next input in the history.
+/// - `Key::CtrlR`: Enters incremental reverse history search; see [`reverse_search`] for details.
 ///
 /// Other editing operations are handled the same way as in the `readln` function, such as character insertion,
 /// deletion, and cursor movement.
 pub fn pushln<'a>(prompt: &str, history: &'a mut Vec<String>) -> io::Result<&'a str> {
+  pushln_with(prompt, history, None, Keymap::Emacs)
+}
+
+/// Like [`pushln`], but with Tab-triggered completion from `completer` and keys resolved
+/// through `keymap` (see [`Keymap`]) instead of always behaving like Emacs.
+///
+/// # Editing Operations
+///
+/// In addition to the operations `pushln` handles, `Key::Tab` runs `completer` against the
+/// word under the cursor: a single candidate is inserted directly; several candidates narrow
+/// the word to their longest common prefix, and a second consecutive `Tab` prints the full
+/// candidate list below the prompt.
+pub fn pushln_with<'a>(prompt: &str, history: &'a mut Vec<String>, completer: Option<&dyn Completer>, keymap: Keymap) -> io::Result<&'a str> {
   let mut local_history = Vec::new();
   let mut new_buf = String::new();
   let mut pos = 0;
   let mut hpos = history.len();
   let mut buf = &mut new_buf;
   let last_history_idx = history.len().saturating_sub(1);
+  let mut kill_ring = KillRing::new();
+  let mut completion = CompletionState::new();
+  let mut render = RenderState::new();
+  let mut vi_state = ViState::new();
+  let _bracketed_paste = BracketedPasteGuard::enable()?;
 
   loop {
-    promptln(prompt, buf, pos)?;
+    promptln(prompt, buf, pos, &mut render)?;
 
-    match readch(buf, &mut pos)? {
+    let mut state =
+      EditState { kill_ring: &mut kill_ring, completer, completion: &mut completion, render: &mut render, keymap, vi: &mut vi_state };
+    match readch(buf, &mut pos, &mut state)? {
       Key::Enter => break,
       Key::ArrowUp => hpos = hpos.saturating_sub(1),
       Key::ArrowDown => {
@@ -96,20 +423,17 @@ pub fn pushln<'a>(prompt: &str, history: &'a mut Vec<String>) -> io::Result<&'a
           hpos += 1
         }
       }
+      Key::CtrlR => {
+        if reverse_search(history, buf, &mut pos, &mut hpos, &mut render)? {
+          buf = repoint_history(history, &mut local_history, &mut new_buf, last_history_idx, hpos);
+          pos = buf.len();
+        }
+        continue;
+      }
       _ => continue,
     }
 
-    let local_pos = last_history_idx.wrapping_sub(hpos);
-    buf = if let Some(item) = local_history.get_mut(local_pos) {
-      item
-    } else if let Some(item) = history.get(hpos) {
-      // We want to be able to mutate the existing history items during the loop but
-      // keep them the same after we return, that's why we clone them on demand here
-      local_history.push(item.clone());
-      &mut local_history[local_pos]
-    } else {
-      &mut new_buf
-    };
+    buf = repoint_history(history, &mut local_history, &mut new_buf, last_history_idx, hpos);
     pos = buf.len();
   }
 
@@ -162,11 +486,31 @@ pub fn pushln<'a>(prompt: &str, history: &'a mut Vec<String>) -> io::Result<&'a
 /// Other editing operations are handled the same way as in the `readch` function, such as character insertion,
 /// deletion, and cursor movement.
 pub fn readln(prompt: &str, buf: &mut String) -> io::Result<()> {
+  readln_with(prompt, buf, None, Keymap::Emacs)
+}
+
+/// Like [`readln`], but with Tab-triggered completion from `completer` and keys resolved
+/// through `keymap` (see [`Keymap`]) instead of always behaving like Emacs.
+///
+/// # Editing Operations
+///
+/// In addition to the operations `readln` handles, `Key::Tab` runs `completer` against the
+/// word under the cursor: a single candidate is inserted directly; several candidates narrow
+/// the word to their longest common prefix, and a second consecutive `Tab` prints the full
+/// candidate list below the prompt.
+pub fn readln_with(prompt: &str, buf: &mut String, completer: Option<&dyn Completer>, keymap: Keymap) -> io::Result<()> {
   let mut pos = buf.len();
+  let mut kill_ring = KillRing::new();
+  let mut completion = CompletionState::new();
+  let mut render = RenderState::new();
+  let mut vi_state = ViState::new();
+  let _bracketed_paste = BracketedPasteGuard::enable()?;
 
   loop {
-    promptln(prompt, buf, pos)?;
-    if matches!(readch(buf, &mut pos)?, Key::Enter) {
+    promptln(prompt, buf, pos, &mut render)?;
+    let mut state =
+      EditState { kill_ring: &mut kill_ring, completer, completion: &mut completion, render: &mut render, keymap, vi: &mut vi_state };
+    if matches!(readch(buf, &mut pos, &mut state)?, Key::Enter) {
       break;
     }
   }
@@ -175,15 +519,198 @@ pub fn readln(prompt: &str, buf: &mut String) -> io::Result<()> {
   Ok(())
 }
 
-fn promptln(prompt: &str, input: &str, mut cursor: usize) -> io::Result<()> {
-  print!("{}\r{}{}\r", CLEAR, prompt, input);
-  cursor += prompt.len();
-  if cursor > 0 {
-    print!("\x1b[{}C", cursor);
+/// Runs an incremental reverse history search (`Ctrl-R`), replacing the prompt with
+/// `(reverse-i-search)'<query>': ` while it's active.
+///
+/// Saves `buf`/`pos` on entry and restores them on abort (`Escape`/`Ctrl-G`), or if `Enter` is
+/// pressed with no match found. On `Enter` with a match, `hpos` is updated to point at it and
+/// this returns `true` *without* writing the match into `buf` — `buf` may not be the slot that
+/// `hpos` now refers to, so the caller is responsible for repointing it first (the same way it
+/// would for an ArrowUp/ArrowDown move) via [`repoint_history`]. Each typed character extends
+/// the query and re-searches from the newest entry; `Ctrl-R` again jumps to the next-older match
+/// for the same query; `Backspace` shortens the query.
+fn reverse_search(history: &[String], buf: &mut String, pos: &mut usize, hpos: &mut usize, render: &mut RenderState) -> io::Result<bool> {
+  let saved_buf = buf.clone();
+  let saved_pos = *pos;
+  let mut query = String::new();
+  let mut before = history.len();
+
+  loop {
+    let found = search_backward(history, &query, before);
+    let shown = found.map(|(_, text)| text).unwrap_or(saved_buf.as_str());
+    let label = format!("(reverse-i-search)'{}': ", query);
+    promptln(&label, shown, shown.len(), render)?;
+
+    match read_key()? {
+      Key::CtrlR => {
+        if let Some((idx, _)) = found {
+          before = idx;
+        }
+      }
+      Key::Backspace if query.pop().is_some() => {
+        before = history.len();
+      }
+      Key::Enter => {
+        if let Some((idx, _)) = found {
+          *hpos = idx;
+          return Ok(true);
+        }
+        return Ok(false);
+      }
+      Key::Char(ch) => {
+        query.push(ch);
+        before = history.len();
+      }
+      Key::Escape | Key::CtrlG | Key::NA => {
+        buf.clear();
+        buf.push_str(&saved_buf);
+        *pos = saved_pos;
+        return Ok(false);
+      }
+      _ => {}
+    }
+  }
+}
+
+/// Returns the `local_history` slot `hpos` refers to (cloning it from `history` on first visit,
+/// same as an ArrowUp/ArrowDown step would), or `new_buf` if `hpos` is past the end of history.
+/// Unlike the single-step arrow case, `hpos` may jump by more than one slot at a time (e.g. after
+/// a Ctrl-R match), so this fills every slot between the last cached one and `hpos` rather than
+/// assuming only the very next slot can be missing.
+fn repoint_history<'a>(history: &[String], local_history: &'a mut Vec<String>, new_buf: &'a mut String, last_history_idx: usize, hpos: usize) -> &'a mut String {
+  if hpos >= history.len() {
+    return new_buf;
+  }
+
+  let local_pos = last_history_idx - hpos;
+  while local_history.len() <= local_pos {
+    let idx = last_history_idx - local_history.len();
+    local_history.push(history[idx].clone());
+  }
+
+  &mut local_history[local_pos]
+}
+
+/// Most recent entry in `history[..before]` containing `query` as a substring, searching
+/// backward from the end.
+fn search_backward<'h>(history: &'h [String], query: &str, before: usize) -> Option<(usize, &'h str)> {
+  if query.is_empty() {
+    return None;
+  }
+  history[..before.min(history.len())].iter().enumerate().rev().find(|(_, entry)| entry.contains(query)).map(|(idx, entry)| (idx, entry.as_str()))
+}
+
+/// Tracks where the previous [`promptln`] call left things, so the next call can find its way
+/// back to the prompt's start row before redrawing.
+///
+/// `rows` is how many rows below the prompt's first row the previous render used (from the
+/// prompt+input wrapping across the terminal width) — used to erase every stale row, so
+/// shrinking input doesn't leave garbage trailing rows behind.
+///
+/// `cursor_row` is the row the *physical terminal cursor* was actually left parked at, which is
+/// not always `rows`: a previous call ending with the edit cursor above the last wrapped row
+/// (e.g. after Ctrl-A, or moving left post-wrap) parks the terminal cursor there instead. Using
+/// `rows` for the entry up-move in that case overshoots past the prompt's first row and clears
+/// whatever was printed before it.
+#[derive(Debug, Default)]
+pub struct RenderState {
+  rows: usize,
+  cursor_row: usize,
+}
+
+impl RenderState {
+  pub fn new() -> Self {
+    Self::default()
+  }
+}
+
+/// Rows of terminal height `text` occupies at `cols` columns wide, treating embedded `\n`s as
+/// forced row breaks in addition to wrapping.
+fn visual_rows(text: &str, cols: usize) -> usize {
+  text
+    .split('\n')
+    .map(|line| {
+      let w = line.width();
+      if w == 0 {
+        1
+      } else {
+        w.div_ceil(cols)
+      }
+    })
+    .sum()
+}
+
+/// The (row, col) the cursor sits at, 0-indexed from the prompt's first row, once
+/// `prompt` + `input[..cursor]` has wrapped across `cols` columns.
+fn cursor_row_col(prompt: &str, input: &str, cursor: usize, cols: usize) -> (usize, usize) {
+  let prefix = format!("{}{}", prompt, &input[..cursor]);
+  let mut rows = 0;
+  let mut lines = prefix.split('\n').peekable();
+
+  while let Some(line) = lines.next() {
+    let w = line.width();
+    if lines.peek().is_some() {
+      rows += if w == 0 { 1 } else { w.div_ceil(cols) };
+    } else if w == 0 {
+      return (rows, 0);
+    } else {
+      // Match `visual_rows`'s div_ceil row count: a width that's an exact multiple of `cols`
+      // lands on the last column of the previous row, not column 0 of a new one.
+      let last_row = w.div_ceil(cols) - 1;
+      return (rows + last_row, w - last_row * cols);
+    }
   }
+
+  (rows, 0)
+}
+
+fn promptln(prompt: &str, input: &str, cursor: usize, render: &mut RenderState) -> io::Result<()> {
+  let cols = term_width().max(1);
+
+  if render.cursor_row > 0 {
+    print!("\x1b[{}A", render.cursor_row);
+  }
+  print!("\r");
+  for row in 0..=render.rows {
+    print!("{}", CLEAR);
+    if row < render.rows {
+      println!();
+    }
+  }
+  if render.rows > 0 {
+    print!("\x1b[{}A", render.rows);
+  }
+
+  print!("\r{}{}", prompt, input);
+
+  let end_row = visual_rows(&format!("{}{}", prompt, input), cols) - 1;
+  render.rows = end_row;
+
+  let (cursor_row, cursor_col) = cursor_row_col(prompt, input, cursor, cols);
+  render.cursor_row = cursor_row;
+  if end_row > cursor_row {
+    print!("\x1b[{}A", end_row - cursor_row);
+  }
+  print!("\r");
+  if cursor_col > 0 {
+    print!("\x1b[{}C", cursor_col);
+  }
+
   io::stdout().flush()
 }
 
+/// The per-keystroke editing state [`readch`] threads through, bundled into one struct instead
+/// of one positional parameter per feature (kill ring, completion, render tracking, keymap,
+/// Vi mode) now that the list keeps growing with every new editing feature.
+pub struct EditState<'a> {
+  pub kill_ring: &'a mut KillRing,
+  pub completer: Option<&'a dyn Completer>,
+  pub completion: &'a mut CompletionState,
+  pub render: &'a mut RenderState,
+  pub keymap: Keymap,
+  pub vi: &'a mut ViState,
+}
+
 /// Reads a single byte of user input, allowing basic editing operations with a cursor.
 ///
 /// # Blocking
@@ -196,6 +723,8 @@ fn promptln(prompt: &str, input: &str, mut cursor: usize) -> io::Result<()> {
 ///
 /// * `buf` - A mutable reference to the buffer where user input is stored.
 /// * `pos` - A mutable reference to the cursor position.
+/// * `state` - The rest of the editing state (kill ring, completer, completion/render tracking,
+///   keymap, Vi mode); see [`EditState`].
 ///
 /// # Returns
 ///
@@ -205,13 +734,25 @@ fn promptln(prompt: &str, input: &str, mut cursor: usize) -> io::Result<()> {
 ///
 /// ```rust
 /// use std::io;
-/// use your_crate_name::stdin_edit;
+/// use your_crate_name::{readch, CompletionState, EditState, Keymap, KillRing, RenderState, ViState};
 ///
 /// let mut buffer = String::new();
 /// let mut cursor_position = 0;
+/// let mut kill_ring = KillRing::new();
+/// let mut completion = CompletionState::new();
+/// let mut render = RenderState::new();
+/// let mut vi_state = ViState::new();
+/// let mut state = EditState {
+///     kill_ring: &mut kill_ring,
+///     completer: None,
+///     completion: &mut completion,
+///     render: &mut render,
+///     keymap: Keymap::Emacs,
+///     vi: &mut vi_state,
+/// };
 ///
 /// loop {
-///     match readch(&mut buffer, &mut cursor_position) {
+///     match readch(&mut buffer, &mut cursor_position, &mut state) {
 ///         Ok(key) => {
 ///             // Handle the key or break the loop on a specific condition
 ///         }
@@ -225,52 +766,257 @@ fn promptln(prompt: &str, input: &str, mut cursor: usize) -> io::Result<()> {
 ///
 /// # Editing Operations
 ///
-/// - `Key::Char(ch)`: Inserts the character `ch` at the current cursor position.
-/// - `Key::Backspace`: Deletes the character before the cursor position.
-/// - `Key::ArrowLeft`: Moves the cursor one position to the left.
-/// - `Key::ArrowRight`: Moves the cursor one position to the right.
-/// - `Key::CtrlBackspace`: Deletes the word before the cursor position.
-/// - `Key::CtrlArrowLeft`: Moves the cursor to the beginning of the previous word.
-/// - `Key::CtrlArrowRight`: Moves the cursor to the beginning of the next word.
-pub fn readch(buf: &mut String, pos: &mut usize) -> io::Result<Key> {
+/// The `key` read is first resolved to an [`Action`] through `state.keymap` (see [`Keymap`] for
+/// what each profile binds), then applied:
+///
+/// - Inserting a character at the current cursor position.
+/// - Deleting the character before/after the cursor.
+/// - Moving the cursor by one character or one word.
+/// - Killing to end/start of line or the previous word into `state.kill_ring`.
+/// - Yanking the most recent kill, or yank-pop to the next-older entry.
+/// - Jumping to the start/end of the line.
+/// - Running `state.completer` (if any) against the word under the cursor; see [`readln_with`]
+///   for the full completion behavior.
+/// - Reading the rest of a bracketed-paste payload and inserting it verbatim as a single edit,
+///   so embedded newlines don't submit the line early.
+/// - For `Keymap::Vi`, switching `state.vi` between insert and normal mode.
+pub fn readch(buf: &mut String, pos: &mut usize, state: &mut EditState) -> io::Result<Key> {
   let key = read_key()?;
-  match key {
-    Key::Char(ch) => {
+  if !matches!(key, Key::CtrlY | Key::MetaY) {
+    state.kill_ring.last_yank_len = None;
+  }
+  if !matches!(key, Key::Tab) {
+    state.completion.pending = false;
+  }
+
+  let action = state.keymap.resolve(key, state.vi);
+  if !matches!(action, Action::KillWordBackward | Action::KillLine | Action::KillLineBackward) {
+    state.kill_ring.last_kill = None;
+  }
+
+  match action {
+    Action::InsertChar(ch) => {
       buf.insert(*pos, ch);
-      *pos += 1;
+      *pos += ch.len_utf8();
     }
-    Key::Backspace => {
-      if *pos > 0 {
-        *pos -= 1;
-        buf.remove(*pos);
-      }
+    Action::DeleteCharBackward if *pos > 0 => {
+      let prev = prev_grapheme_boundary(buf, *pos);
+      buf.replace_range(prev..*pos, "");
+      *pos = prev;
     }
-    Key::ArrowLeft => {
-      *pos = pos.saturating_sub(1);
+    Action::DeleteCharForward if *pos < buf.len() => {
+      let next = next_grapheme_boundary(buf, *pos);
+      buf.replace_range(*pos..next, "");
     }
-    Key::ArrowRight => {
-      if *pos < buf.len() {
-        *pos += 1;
-      }
+    Action::MoveBackwardChar => {
+      *pos = prev_grapheme_boundary(buf, *pos);
+    }
+    Action::MoveForwardChar => {
+      *pos = next_grapheme_boundary(buf, *pos);
     }
-    Key::CtrlBackspace => {
-      let idx = buf[..*pos].as_bytes().iter().rposition(|c| c == &b' ').unwrap_or_default();
+    Action::KillWordBackward => {
+      let idx = prev_word_boundary(buf, *pos);
+      let killed = buf[idx..*pos].to_owned();
       buf.replace_range(idx..*pos, "");
       *pos = idx;
+      state.kill_ring.push(&killed, KillDir::Backward);
     }
-    Key::CtrlArrowLeft => {
-      *pos = buf[..*pos].as_bytes().iter().rposition(|c| c == &b' ').unwrap_or_default();
+    Action::MoveBackwardWord => {
+      *pos = prev_word_boundary(buf, *pos);
     }
-    Key::CtrlArrowRight => {
-      let bytes = buf.as_bytes();
-      while *pos < buf.len() {
-        *pos += 1;
-        if bytes.get(*pos).is_some_and(|b| b == &b' ') {
-          break;
-        }
+    Action::MoveForwardWord => {
+      *pos = next_word_boundary(buf, *pos);
+    }
+    Action::KillLine => {
+      let killed = buf[*pos..].to_owned();
+      buf.truncate(*pos);
+      state.kill_ring.push(&killed, KillDir::Forward);
+    }
+    Action::KillLineBackward => {
+      let killed = buf[..*pos].to_owned();
+      buf.replace_range(..*pos, "");
+      *pos = 0;
+      state.kill_ring.push(&killed, KillDir::Backward);
+    }
+    Action::Yank => state.kill_ring.yank(buf, pos),
+    Action::YankPop => state.kill_ring.yank_pop(buf, pos),
+    Action::BeginningOfLine => *pos = 0,
+    Action::EndOfLine => *pos = buf.len(),
+    Action::Complete => {
+      if let Some(completer) = state.completer {
+        complete(buf, pos, completer, state.completion, state.render)?;
       }
     }
+    Action::Paste => {
+      let payload = read_paste()?;
+      buf.insert_str(*pos, &payload);
+      *pos += payload.len();
+    }
+    Action::EnterNormalMode => state.vi.insert = false,
+    Action::EnterInsertMode => state.vi.insert = true,
+    Action::EnterInsertModeAfter => {
+      *pos = next_grapheme_boundary(buf, *pos);
+      state.vi.insert = true;
+    }
     _ => (),
   }
   Ok(key)
 }
+
+/// Runs `completer` against the word under the cursor and applies the result: a single
+/// candidate is spliced in directly; several candidates narrow the word to their longest
+/// common prefix, and a second consecutive `Tab` at that prefix prints the full candidate
+/// list below the prompt.
+fn complete(buf: &mut String, pos: &mut usize, completer: &dyn Completer, completion: &mut CompletionState, render: &mut RenderState) -> io::Result<()> {
+  let (start, candidates) = completer.complete(buf, *pos);
+
+  let Some(start) = valid_completion_start(buf, start, *pos) else {
+    completion.pending = false;
+    return Ok(());
+  };
+
+  match candidates.as_slice() {
+    [] => completion.pending = false,
+    [only] => {
+      buf.replace_range(start..*pos, only);
+      *pos = start + only.len();
+      completion.pending = false;
+    }
+    _ => {
+      let prefix = longest_common_prefix(&candidates);
+      if buf[start..*pos] != prefix {
+        buf.replace_range(start..*pos, &prefix);
+        *pos = start + prefix.len();
+        completion.pending = true;
+      } else if completion.pending {
+        // Print the list below the last row the prompt+input occupies and leave it there: the
+        // next `promptln` starts from a fresh render state on the row under the list, so it
+        // redraws the prompt below the list instead of erasing it.
+        if render.rows > render.cursor_row {
+          print!("\x1b[{}B", render.rows - render.cursor_row);
+        }
+        let list = candidates.join("  ");
+        println!("\n\r{}{}", CLEAR, list);
+        io::stdout().flush()?;
+        *render = RenderState::new();
+      } else {
+        completion.pending = true;
+      }
+    }
+  }
+
+  Ok(())
+}
+
+/// A `Completer`-returned `start` is only safe to slice `buf[start..pos]` with if it's at or
+/// before `pos` and lands on a UTF-8 char boundary. A third-party `Completer` impl can easily
+/// get either wrong against a `line` it only sees as `&str`, so this rejects anything unusable
+/// instead of letting `complete` panic on the slice.
+fn valid_completion_start(buf: &str, start: usize, pos: usize) -> Option<usize> {
+  (start <= pos && buf.is_char_boundary(start)).then_some(start)
+}
+
+/// Byte offset of the start of the grapheme cluster immediately before `pos`.
+fn prev_grapheme_boundary(buf: &str, pos: usize) -> usize {
+  buf[..pos].grapheme_indices(true).next_back().map(|(idx, _)| idx).unwrap_or(0)
+}
+
+/// Byte offset of the start of the grapheme cluster immediately after `pos`.
+fn next_grapheme_boundary(buf: &str, pos: usize) -> usize {
+  buf[pos..].grapheme_indices(true).nth(1).map(|(idx, _)| pos + idx).unwrap_or(buf.len())
+}
+
+/// Byte offset of the start of the word boundary immediately before `pos`.
+fn prev_word_boundary(buf: &str, pos: usize) -> usize {
+  buf[..pos]
+    .split_word_bound_indices()
+    .rev()
+    .find(|(_, word)| !word.trim().is_empty())
+    .map(|(idx, _)| idx)
+    .unwrap_or(0)
+}
+
+/// Byte offset of the start of the word boundary immediately after `pos`.
+fn next_word_boundary(buf: &str, pos: usize) -> usize {
+  buf[pos..]
+    .split_word_bound_indices()
+    .find(|(_, word)| !word.trim().is_empty())
+    .map(|(idx, word)| pos + idx + word.len())
+    .unwrap_or(buf.len())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn visual_rows_single_line_fits() {
+    assert_eq!(visual_rows("hello", 10), 1);
+  }
+
+  #[test]
+  fn visual_rows_wraps_across_cols() {
+    assert_eq!(visual_rows("hello world!", 5), 3);
+  }
+
+  #[test]
+  fn visual_rows_exact_multiple_of_cols_does_not_add_a_row() {
+    assert_eq!(visual_rows("0123456789", 10), 1);
+  }
+
+  #[test]
+  fn visual_rows_counts_embedded_newlines_as_forced_breaks() {
+    assert_eq!(visual_rows("ab\ncd", 10), 2);
+  }
+
+  #[test]
+  fn cursor_row_col_before_any_wrap() {
+    assert_eq!(cursor_row_col("> ", "0123456789", 0, 10), (0, 2));
+  }
+
+  #[test]
+  fn cursor_row_col_agrees_with_visual_rows_once_wrapped() {
+    let (prompt, input, cols) = ("> ", "0123456789", 10);
+    let total_rows = visual_rows(&format!("{prompt}{input}"), cols);
+    let (row, _col) = cursor_row_col(prompt, input, input.len(), cols);
+    assert_eq!(row, total_rows - 1);
+  }
+
+  #[test]
+  fn kill_ring_merges_consecutive_same_direction_kills() {
+    let mut ring = KillRing::new();
+    ring.push("world", KillDir::Forward);
+    ring.push("!", KillDir::Forward);
+
+    let mut buf = String::new();
+    let mut pos = 0;
+    ring.yank(&mut buf, &mut pos);
+    assert_eq!(buf, "world!");
+  }
+
+  #[test]
+  fn kill_ring_keeps_opposite_direction_kills_as_separate_entries() {
+    let mut ring = KillRing::new();
+    ring.push("a", KillDir::Forward);
+    ring.push("b", KillDir::Backward);
+
+    let mut buf = String::new();
+    let mut pos = 0;
+    ring.yank(&mut buf, &mut pos);
+    assert_eq!(buf, "b");
+
+    ring.yank_pop(&mut buf, &mut pos);
+    assert_eq!(buf, "a");
+  }
+
+  #[test]
+  fn kill_ring_yank_pop_without_a_preceding_yank_is_a_noop() {
+    let mut ring = KillRing::new();
+    ring.push("x", KillDir::Forward);
+
+    let mut buf = String::from("hi");
+    let mut pos = 2;
+    ring.yank_pop(&mut buf, &mut pos);
+    assert_eq!(buf, "hi");
+  }
+}
@@ -1,42 +1,119 @@
 use super::Key;
+use std::cell::RefCell;
+use std::collections::VecDeque;
 use std::io::{self};
 use std::os::raw::{c_char, c_int};
 
+thread_local! {
+  /// Bytes read while probing an escape sequence that turned out not to match any known
+  /// sequence, queued up so the next [`getch`] calls hand them back instead of dropping them.
+  static PENDING: RefCell<VecDeque<u8>> = const { RefCell::new(VecDeque::new()) };
+}
+
 pub fn read_key() -> io::Result<Key> {
   Ok(match getch()? {
+    1 => Key::CtrlA,
+    2 => Key::CtrlB,
+    5 => Key::CtrlE,
+    6 => Key::CtrlF,
+    7 => Key::CtrlG,
     8 | 23 => Key::CtrlBackspace,
+    9 => Key::Tab,
     10 => Key::Enter,
+    11 => Key::CtrlK,
+    18 => Key::CtrlR,
+    21 => Key::CtrlU,
+    25 => Key::CtrlY,
     27 => parse_esc_seq()?,
     127 => Key::Backspace,
-    n if n > 31 => Key::Char(n as char),
+    n if n > 31 => Key::Char(read_utf8_char(n)?),
     _ => Key::NA,
   })
 }
 
+/// Reads the remaining continuation bytes of a UTF-8 sequence that started with `lead`
+/// and decodes the full sequence into a `char`.
+fn read_utf8_char(lead: u8) -> io::Result<char> {
+  let extra = if lead & 0xE0 == 0xC0 {
+    1
+  } else if lead & 0xF0 == 0xE0 {
+    2
+  } else if lead & 0xF8 == 0xF0 {
+    3
+  } else {
+    0
+  };
+
+  let mut bytes = vec![lead];
+  for _ in 0..extra {
+    bytes.push(getch()?);
+  }
+
+  Ok(std::str::from_utf8(&bytes).ok().and_then(|s| s.chars().next()).unwrap_or(char::REPLACEMENT_CHARACTER))
+}
+
+/// How long `parse_esc_seq` waits for the byte following an `Escape` before giving up and
+/// reporting a lone `Escape` key press. Real escape sequences (arrow keys, bracketed paste,
+/// ...) arrive with their remaining bytes already queued by the terminal, well inside this
+/// window; a human pressing just Escape never sends a follow-up byte at all.
+const ESC_TIMEOUT_MS: i32 = 25;
+
+/// Disambiguates a bare `Escape` key press from the start of a multi-byte escape sequence by
+/// tracking, byte by byte, which [`ESC_SEQ_LIST`] candidates still share the bytes read so far.
+///
+/// As soon as the read bytes no longer form the prefix of any candidate, parsing stops there
+/// instead of always consuming a fixed number of further bytes — and every byte read during
+/// the failed probe is queued in [`PENDING`] so it's delivered as its own key on the next
+/// [`read_key`] call rather than silently discarded.
+///
+/// The very first byte is read with a short timeout ([`ESC_TIMEOUT_MS`]) rather than a blocking
+/// [`getch`]: without it, a lone Escape press would sit unread until the *next* keystroke came
+/// in to disambiguate it, which looks exactly like a hang to whoever just pressed Escape.
 fn parse_esc_seq() -> io::Result<Key> {
-  let mut ch;
   let mut pos = 0;
+  let mut consumed = Vec::new();
 
-  while pos < ESC_SEQ_LEN + 1 {
-    ch = getch()?;
-    for (ref key, seq) in ESC_SEQ_LIST {
-      if pos >= seq.len() {
-        continue;
+  loop {
+    let ch = if pos == 0 {
+      match getch_timeout(ESC_TIMEOUT_MS)? {
+        Some(ch) => ch,
+        None => return Ok(Key::Escape),
       }
+    } else {
+      getch()?
+    };
+    consumed.push(ch);
 
-      if seq[pos] == ch && seq.len() - 1 == pos {
-        return Ok(*key);
+    let mut has_prefix_match = false;
+    for (key, seq) in ESC_SEQ_LIST {
+      if !seq.starts_with(&consumed) {
+        continue;
+      }
+      has_prefix_match = true;
+      if seq.len() == pos + 1 {
+        return Ok(key);
       }
     }
+
+    if !has_prefix_match {
+      PENDING.with(|p| p.borrow_mut().extend(consumed));
+      return Ok(Key::Escape);
+    }
+
     pos += 1;
   }
-
-  Ok(Key::NA)
 }
 
 type EscapeSequence = (Key, &'static [u8]);
 
-const ESC_SEQ_LEN: usize = 6;
+// `Key::MetaY` has no entry here: a real `ESC y` (Alt+Y) arrives as both bytes already
+// buffered by the terminal, which reads identically to someone pressing Escape and then
+// typing a literal `y` within `ESC_TIMEOUT_MS` — a timeout can tell "nothing followed" from
+// "something followed quickly" but not "sequence" from "two fast keypresses". Binding it would
+// silently eat the first `y` of any word typed right after leaving Vi insert mode. Meta-Y
+// stays reachable on Windows, where Alt+Y arrives as its own modifier flag rather than a byte
+// sequence.
+const ESC_SEQ_LEN: usize = 7;
 const ESC_SEQ_LIST: [EscapeSequence; ESC_SEQ_LEN] = [
   (Key::ArrowUp, b"[A"),
   (Key::ArrowDown, b"[B"),
@@ -44,8 +121,44 @@ const ESC_SEQ_LIST: [EscapeSequence; ESC_SEQ_LEN] = [
   (Key::ArrowLeft, b"[D"),
   (Key::CtrlArrowRight, b"[1;5C"),
   (Key::CtrlArrowLeft, b"[1;5D"),
+  (Key::PasteStart, b"[200~"),
 ];
 
+/// Reads the rest of a bracketed-paste payload after a `Key::PasteStart`, treating every byte
+/// (including newlines) as literal content until the `\x1b[201~` paste-end sequence is seen.
+/// `\r`/`\r\n` line endings in the payload are normalized to `\n`; see
+/// [`super::normalize_paste_newlines`].
+pub fn read_paste() -> io::Result<String> {
+  const PASTE_END: &[u8] = b"[201~";
+  let mut bytes = Vec::new();
+
+  loop {
+    let ch = getch()?;
+    if ch != 27 {
+      bytes.push(ch);
+      continue;
+    }
+
+    let mut probed = Vec::new();
+    let mut matched = true;
+    for &expected in PASTE_END {
+      let b = getch()?;
+      probed.push(b);
+      if b != expected {
+        matched = false;
+        break;
+      }
+    }
+    if matched {
+      break;
+    }
+    bytes.push(27);
+    bytes.extend(probed);
+  }
+
+  Ok(String::from_utf8_lossy(&super::normalize_paste_newlines(&bytes)).into_owned())
+}
+
 extern "C" {
   fn tcgetattr(fd: c_int, termios_p: *mut libc::termios) -> c_int;
   fn tcsetattr(fd: c_int, optional_actions: c_int, termios_p: *const libc::termios) -> c_int;
@@ -53,11 +166,28 @@ extern "C" {
   fn read(fd: c_int, buf: *mut c_char, count: usize) -> isize;
 }
 
+/// Queries the terminal width in columns via `TIOCGWINSZ`, falling back to 80 columns if
+/// stdout isn't a terminal or the query fails.
+pub fn term_width() -> usize {
+  let mut ws: libc::winsize = unsafe { std::mem::zeroed() };
+  let queried = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut ws) } == 0;
+
+  if queried && ws.ws_col > 0 {
+    ws.ws_col as usize
+  } else {
+    80
+  }
+}
+
 const STDIN_FILENO: c_int = 0;
 const TCSANOW: c_int = 0;
 const TCSADRAIN: c_int = 1;
 
 fn getch() -> io::Result<u8> {
+  if let Some(byte) = PENDING.with(|p| p.borrow_mut().pop_front()) {
+    return Ok(byte);
+  }
+
   let mut buf: c_char = 0;
   let mut old: libc::termios = unsafe { std::mem::zeroed() };
 
@@ -93,3 +223,55 @@ fn getch() -> io::Result<u8> {
 
   Ok(buf as u8)
 }
+
+/// Like [`getch`], but gives up and returns `Ok(None)` if no byte arrives within `timeout_ms`
+/// instead of blocking forever, by polling `stdin` before the read.
+fn getch_timeout(timeout_ms: i32) -> io::Result<Option<u8>> {
+  if let Some(byte) = PENDING.with(|p| p.borrow_mut().pop_front()) {
+    return Ok(Some(byte));
+  }
+
+  let mut buf: c_char = 0;
+  let mut old: libc::termios = unsafe { std::mem::zeroed() };
+
+  unsafe {
+    if fflush(std::ptr::null_mut()) < 0 {
+      return Err(io::Error::last_os_error());
+    }
+
+    if tcgetattr(STDIN_FILENO, &mut old) < 0 {
+      return Err(io::Error::last_os_error());
+    }
+
+    old.c_lflag &= !libc::ICANON;
+    old.c_lflag &= !libc::ECHO;
+    old.c_cc[libc::VMIN] = 1;
+    old.c_cc[libc::VTIME] = 0;
+
+    if tcsetattr(STDIN_FILENO, TCSANOW, &old) < 0 {
+      return Err(io::Error::last_os_error());
+    }
+
+    let mut pfd = libc::pollfd { fd: STDIN_FILENO, events: libc::POLLIN, revents: 0 };
+    let ready = libc::poll(&mut pfd, 1, timeout_ms);
+
+    let result = if ready < 0 {
+      Err(io::Error::last_os_error())
+    } else if ready == 0 || pfd.revents & libc::POLLIN == 0 {
+      Ok(None)
+    } else if read(STDIN_FILENO, &mut buf, 1) < 0 {
+      Err(io::Error::last_os_error())
+    } else {
+      Ok(Some(buf as u8))
+    };
+
+    old.c_lflag |= libc::ICANON;
+    old.c_lflag |= libc::ECHO;
+
+    if tcsetattr(STDIN_FILENO, TCSADRAIN, &old) < 0 {
+      return Err(io::Error::last_os_error());
+    }
+
+    result
+  }
+}
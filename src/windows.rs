@@ -1,13 +1,75 @@
 use crate::Key;
+use std::cell::RefCell;
+use std::collections::VecDeque;
 use std::io;
 use windows_sys::Win32::{
-  System::Console::{self, GetStdHandle, ReadConsoleInputA, INPUT_RECORD, STD_INPUT_HANDLE},
+  Foundation::{WAIT_OBJECT_0, WAIT_TIMEOUT},
+  System::{
+    Console::{self, GetStdHandle, ReadConsoleInputA, CONSOLE_SCREEN_BUFFER_INFO, INPUT_RECORD, STD_INPUT_HANDLE, STD_OUTPUT_HANDLE},
+    Threading::WaitForSingleObject,
+  },
   UI::Input::KeyboardAndMouse as Wk,
 };
 
 const KEY_EVENT: u16 = Console::KEY_EVENT as u16;
 
+/// How long [`parse_esc_seq`] waits for the byte following an `Escape` before giving up and
+/// reporting a lone `Escape` key press, mirroring `unix::ESC_TIMEOUT_MS`.
+const ESC_TIMEOUT_MS: u32 = 25;
+
+thread_local! {
+  /// Raw bytes read while probing for the bracketed-paste start sequence that turned out not
+  /// to match, queued up so the next [`read_key`]/[`read_raw_byte`] call hands them back as
+  /// their own key/byte instead of dropping them.
+  static PENDING: RefCell<VecDeque<u8>> = const { RefCell::new(VecDeque::new()) };
+}
+
+/// Decodes a raw byte queued up by [`parse_esc_seq`] the same way [`read_key`] would have
+/// decoded it had it arrived as a normal console key event's `AsciiChar`.
+fn decode_raw_byte(byte: u8) -> Key {
+  match byte {
+    1 => Key::CtrlA,
+    2 => Key::CtrlB,
+    5 => Key::CtrlE,
+    6 => Key::CtrlF,
+    7 => Key::CtrlG,
+    8 => Key::Backspace,
+    23 | 127 => Key::CtrlBackspace,
+    9 => Key::Tab,
+    10 | 13 => Key::Enter,
+    11 => Key::CtrlK,
+    18 => Key::CtrlR,
+    21 => Key::CtrlU,
+    25 => Key::CtrlY,
+    27 => Key::Escape,
+    n if n > 31 => Key::Char(n as char),
+    _ => Key::NA,
+  }
+}
+
+/// Queries the terminal width in columns via `GetConsoleScreenBufferInfo`, falling back to 80
+/// columns if stdout isn't a console or the query fails.
+pub fn term_width() -> usize {
+  unsafe {
+    let h_stdout = GetStdHandle(STD_OUTPUT_HANDLE);
+    let mut info: CONSOLE_SCREEN_BUFFER_INFO = std::mem::zeroed();
+
+    if Console::GetConsoleScreenBufferInfo(h_stdout, &mut info) != 0 {
+      let width = info.srWindow.Right - info.srWindow.Left + 1;
+      if width > 0 {
+        return width as usize;
+      }
+    }
+  }
+
+  80
+}
+
 pub fn read_key() -> io::Result<Key> {
+  if let Some(byte) = PENDING.with(|p| p.borrow_mut().pop_front()) {
+    return Ok(decode_raw_byte(byte));
+  }
+
   let mut dw_events_read: u32 = 0;
 
   unsafe {
@@ -17,16 +79,29 @@ pub fn read_key() -> io::Result<Key> {
     while ReadConsoleInputA(h_stdin, &mut ir_input_record, 1, &mut dw_events_read) != 0 {
       if ir_input_record.EventType == KEY_EVENT && ir_input_record.Event.KeyEvent.bKeyDown != 0 {
         let ctrl = ir_input_record.Event.KeyEvent.dwControlKeyState & 0x0008 != 0;
+        let alt = ir_input_record.Event.KeyEvent.dwControlKeyState & 0x0003 != 0;
         return Ok(match ir_input_record.Event.KeyEvent.wVirtualKeyCode {
           Wk::VK_W | Wk::VK_BACK if ctrl => Key::CtrlBackspace,
           Wk::VK_LEFT if ctrl => Key::CtrlArrowLeft,
           Wk::VK_RIGHT if ctrl => Key::CtrlArrowRight,
+          Wk::VK_A if ctrl => Key::CtrlA,
+          Wk::VK_E if ctrl => Key::CtrlE,
+          Wk::VK_F if ctrl => Key::CtrlF,
+          Wk::VK_B if ctrl => Key::CtrlB,
+          Wk::VK_K if ctrl => Key::CtrlK,
+          Wk::VK_U if ctrl => Key::CtrlU,
+          Wk::VK_Y if ctrl => Key::CtrlY,
+          Wk::VK_R if ctrl => Key::CtrlR,
+          Wk::VK_G if ctrl => Key::CtrlG,
+          Wk::VK_Y if alt => Key::MetaY,
           Wk::VK_RETURN => Key::Enter,
+          Wk::VK_TAB => Key::Tab,
           Wk::VK_BACK => Key::Backspace,
           Wk::VK_UP => Key::ArrowUp,
           Wk::VK_DOWN => Key::ArrowDown,
           Wk::VK_LEFT => Key::ArrowLeft,
           Wk::VK_RIGHT => Key::ArrowRight,
+          Wk::VK_ESCAPE => parse_esc_seq()?,
           _ if !ctrl && ir_input_record.Event.KeyEvent.uChar.AsciiChar != 0 => Key::Char(ir_input_record.Event.KeyEvent.uChar.AsciiChar as char),
           _ => Key::NA,
         });
@@ -40,3 +115,123 @@ pub fn read_key() -> io::Result<Key> {
 
   Ok(Key::NA)
 }
+
+/// Reads a single raw byte from the console input queue, bypassing the `Key` translation in
+/// [`read_key`]. Used to read the bytes of a VT escape sequence that terminals running in
+/// virtual-terminal-input mode (e.g. bracketed paste) send as plain `AsciiChar` key events.
+fn read_raw_byte() -> io::Result<u8> {
+  if let Some(byte) = PENDING.with(|p| p.borrow_mut().pop_front()) {
+    return Ok(byte);
+  }
+
+  let mut dw_events_read: u32 = 0;
+
+  unsafe {
+    let h_stdin = GetStdHandle(STD_INPUT_HANDLE);
+    let mut ir_input_record: INPUT_RECORD = std::mem::zeroed();
+
+    while ReadConsoleInputA(h_stdin, &mut ir_input_record, 1, &mut dw_events_read) != 0 {
+      if ir_input_record.EventType == KEY_EVENT && ir_input_record.Event.KeyEvent.bKeyDown != 0 {
+        return Ok(ir_input_record.Event.KeyEvent.uChar.AsciiChar as u8);
+      }
+    }
+  }
+
+  Err(io::Error::last_os_error())
+}
+
+/// Like [`read_raw_byte`], but gives up and returns `Ok(None)` if no console input event
+/// arrives within `timeout_ms`, by waiting on the stdin handle with `WaitForSingleObject` before
+/// reading. Non-key events (key-up, resize, focus, ...) re-arm the wait rather than counting as
+/// the awaited byte, the same way [`read_raw_byte`]'s loop skips over them.
+fn read_raw_byte_timeout(timeout_ms: u32) -> io::Result<Option<u8>> {
+  if let Some(byte) = PENDING.with(|p| p.borrow_mut().pop_front()) {
+    return Ok(Some(byte));
+  }
+
+  unsafe {
+    let h_stdin = GetStdHandle(STD_INPUT_HANDLE);
+
+    loop {
+      match WaitForSingleObject(h_stdin, timeout_ms) {
+        WAIT_OBJECT_0 => {}
+        WAIT_TIMEOUT => return Ok(None),
+        _ => return Err(io::Error::last_os_error()),
+      }
+
+      let mut dw_events_read: u32 = 0;
+      let mut ir_input_record: INPUT_RECORD = std::mem::zeroed();
+      if ReadConsoleInputA(h_stdin, &mut ir_input_record, 1, &mut dw_events_read) == 0 {
+        return Err(io::Error::last_os_error());
+      }
+      if dw_events_read == 0 {
+        return Err(io::Error::last_os_error());
+      }
+      if ir_input_record.EventType == KEY_EVENT && ir_input_record.Event.KeyEvent.bKeyDown != 0 {
+        return Ok(Some(ir_input_record.Event.KeyEvent.uChar.AsciiChar as u8));
+      }
+    }
+  }
+}
+
+/// Recognizes the bracketed-paste start sequence (`[200~`) following an `Escape` key event;
+/// anything else is reported as `Key::Escape` (a lone press of the Escape key, e.g. to switch
+/// a `Vi` keymap to normal mode). The mismatching byte, if any, is queued in [`PENDING`] rather
+/// than discarded, so the keystroke it came from is still delivered on the next [`read_key`].
+///
+/// The first byte is read with a short timeout ([`ESC_TIMEOUT_MS`]) instead of a blocking
+/// [`read_raw_byte`]: without it, a lone Escape press would sit unread until the *next*
+/// keystroke came in to disambiguate it, which looks exactly like a hang.
+fn parse_esc_seq() -> io::Result<Key> {
+  const PASTE_START: &[u8] = b"[200~";
+  for (i, &expected) in PASTE_START.iter().enumerate() {
+    let byte = if i == 0 {
+      match read_raw_byte_timeout(ESC_TIMEOUT_MS)? {
+        Some(byte) => byte,
+        None => return Ok(Key::Escape),
+      }
+    } else {
+      read_raw_byte()?
+    };
+    if byte != expected {
+      PENDING.with(|p| p.borrow_mut().push_back(byte));
+      return Ok(Key::Escape);
+    }
+  }
+  Ok(Key::PasteStart)
+}
+
+/// Reads the rest of a bracketed-paste payload after a `Key::PasteStart`, treating every byte
+/// (including newlines) as literal content until the `\x1b[201~` paste-end sequence is seen.
+/// `\r`/`\r\n` line endings in the payload are normalized to `\n`; see
+/// [`crate::normalize_paste_newlines`].
+pub fn read_paste() -> io::Result<String> {
+  const PASTE_END: &[u8] = b"[201~";
+  let mut bytes = Vec::new();
+
+  loop {
+    let ch = read_raw_byte()?;
+    if ch != 27 {
+      bytes.push(ch);
+      continue;
+    }
+
+    let mut probed = Vec::new();
+    let mut matched = true;
+    for &expected in PASTE_END {
+      let b = read_raw_byte()?;
+      probed.push(b);
+      if b != expected {
+        matched = false;
+        break;
+      }
+    }
+    if matched {
+      break;
+    }
+    bytes.push(27);
+    bytes.extend(probed);
+  }
+
+  Ok(String::from_utf8_lossy(&crate::normalize_paste_newlines(&bytes)).into_owned())
+}